@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+
+pub const CACHE_FILE_NAME: &str = ".adita-cache.json";
+
+/// Bump whenever `TypeScriptGenerator`'s output format changes, so a cache
+/// left over from an older adita build doesn't mask content that would now
+/// be generated differently.
+pub const GENERATOR_VERSION: u32 = 1;
+
+/// A 128-bit content hash, assembled from two SipHash passes (the second over
+/// the value paired with a fixed salt string) so a single `DefaultHasher`
+/// collision can't make an output look unchanged when it isn't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentHash(u128);
+
+impl ContentHash {
+    pub fn of<T: Hash>(value: &T) -> Self {
+        let mut low = DefaultHasher::new();
+        value.hash(&mut low);
+
+        let mut high = DefaultHasher::new();
+        (value, "adita-cache-salt").hash(&mut high);
+
+        Self(((high.finish() as u128) << 64) | low.finish() as u128)
+    }
+
+    pub fn into_inner(self) -> u128 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    quick_hash: u128,
+    content_hash: u128,
+}
+
+/// Maps each generated output path to the hashes it had the last time adita
+/// wrote it, persisted as `.adita-cache.json` inside `out_dir`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CacheManifest {
+    #[serde(default)]
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl CacheManifest {
+    pub fn load(out_dir: &Path) -> Self {
+        fs::read_to_string(out_dir.join(CACHE_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, out_dir: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(out_dir.join(CACHE_FILE_NAME), content)?;
+        Ok(())
+    }
+
+    /// Tier one of the incremental check: cheap to compute, cheap to
+    /// compare. A mismatch means the output has definitely changed.
+    pub fn quick_hash_matches(&self, output_path: &Path, quick_hash: u128) -> bool {
+        self.entries
+            .get(output_path)
+            .is_some_and(|entry| entry.quick_hash == quick_hash)
+    }
+
+    /// Tier two: only worth computing once the quick hash matches, to rule
+    /// out a quick-hash collision before trusting that nothing changed.
+    pub fn content_unchanged(&self, output_path: &Path, content_hash: u128) -> bool {
+        self.entries
+            .get(output_path)
+            .is_some_and(|entry| entry.content_hash == content_hash)
+    }
+
+    pub fn record(&mut self, output_path: PathBuf, quick_hash: u128, content_hash: u128) {
+        self.entries.insert(
+            output_path,
+            CacheEntry {
+                quick_hash,
+                content_hash,
+            },
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Result of comparing generated output against what's committed on disk,
+/// without writing anything. Mirrors the "verify nothing changed" check
+/// file-editing tools run before reporting success.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    /// Would be generated now but doesn't exist on disk yet.
+    pub missing: Vec<PathBuf>,
+    /// Exists on disk but its content no longer matches what's generated.
+    pub stale: Vec<PathBuf>,
+    /// Exists on disk but is no longer produced by any source file.
+    pub orphaned: Vec<PathBuf>,
+}
+
+impl CheckReport {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.stale.is_empty() && self.orphaned.is_empty()
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for path in &self.missing {
+            writeln!(f, "  missing:  {}", path.display())?;
+        }
+        for path in &self.stale {
+            writeln!(f, "  stale:    {}", path.display())?;
+        }
+        for path in &self.orphaned {
+            writeln!(f, "  orphaned: {}", path.display())?;
+        }
+        Ok(())
+    }
+}
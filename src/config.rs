@@ -0,0 +1,56 @@
+use serde::Deserialize;
+use std::fs;
+
+use crate::error::Result;
+
+/// Top-level shape of `adita.toml`: a list of named targets, each describing
+/// its own source globs, output directory, and generation options. This
+/// mirrors the manifest-with-environments pattern build tools use so adita
+/// can be run as a single codegen step across a monorepo with several
+/// contract trees.
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    #[serde(rename = "target", default)]
+    pub targets: Vec<Target>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Target {
+    /// Identifies this target in `check` diagnostics, since a
+    /// single `out_dir` on its own doesn't distinguish targets that share
+    /// one (e.g. differing only by `include`/`exclude`).
+    pub name: String,
+
+    /// One or more globs, each resolved relative to the current working
+    /// directory (not the directory containing the config file).
+    pub source: Vec<String>,
+
+    pub out_dir: String,
+
+    /// Always disambiguate identifiers with their input types, even for
+    /// fragment names that don't collide within a file.
+    #[serde(default)]
+    pub force_explicit_identifiers: bool,
+
+    /// Fragment `type` values to keep (e.g. "function", "event"). Empty
+    /// means keep everything.
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Fragment `type` values to drop. Applied after `include`.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Disambiguate same-stem source files by mirroring their subdirectory
+    /// path under `out_dir`, instead of erroring on the collision.
+    #[serde(default)]
+    pub mirror_structure: bool,
+}
+
+impl Config {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let config: Config = toml::from_str(&content)?;
+        Ok(config)
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,8 +12,20 @@ pub enum CodegenError {
     #[error("Glob pattern error: {0}")]
     Glob(#[from] glob::PatternError),
 
+    #[error("Config error: {0}")]
+    Config(#[from] toml::de::Error),
+
     #[error("Missing fragment name")]
     MissingName,
+
+    #[error(
+        "multiple source files map to output {output}: {sources:?} \
+         (pass --mirror-structure to disambiguate by source subdirectory)"
+    )]
+    DuplicateOutput {
+        output: PathBuf,
+        sources: Vec<PathBuf>,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, CodegenError>;
@@ -1,41 +1,56 @@
 use crate::error::{CodegenError, Result};
 use serde::{Deserialize, Serialize};
 
+// Field order below is significant: serde derives serialize struct fields in
+// declaration order, and with `serde_json`'s `preserve_order` feature enabled
+// (backed by `indexmap`) that order survives the round-trip through
+// `serde_json::Value` used by the TypeScript generator. Keep these in
+// ABI-canonical order (`type`, `name`, ...) so generated `as const` literals
+// match the shape tools like viem/abitype expect.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct FragmentInput {
-    #[serde(default)]
-    pub name: Option<String>,
-
     #[serde(rename = "type")]
     pub type_name: String,
 
+    #[serde(default)]
+    pub name: Option<String>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub indexed: Option<bool>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub internal_type: Option<String>,
+
+    /// Present when `type_name` is `tuple`/`tuple[]`/etc., describing the
+    /// struct's own fields so a `tuple` parameter's canonical signature can
+    /// be expanded recursively instead of left as the literal word `tuple`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<FragmentInput>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct FragmentOutput {
-    #[serde(default)]
-    pub name: Option<String>,
-
     #[serde(rename = "type")]
     pub type_name: String,
 
+    #[serde(default)]
+    pub name: Option<String>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub internal_type: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<FragmentOutput>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct Fragment {
-    #[serde(default)]
-    pub name: Option<String>,
-
     #[serde(rename = "type")]
     pub type_name: String,
 
+    #[serde(default)]
+    pub name: Option<String>,
+
     pub inputs: Vec<FragmentInput>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -3,6 +3,7 @@ use std::collections::{HashMap, HashSet};
 
 use crate::error::Result;
 use crate::fragment::Fragment;
+use crate::selectors::{self, FragmentSelector};
 
 pub struct TypeScriptGenerator;
 
@@ -43,7 +44,10 @@ impl TypeScriptGenerator {
         Ok((identifier, declaration))
     }
 
-    pub fn generate_file_content(fragments: Vec<Fragment>) -> Result<Option<String>> {
+    pub fn generate_file_content(
+        fragments: Vec<Fragment>,
+        force_explicit_identifiers: bool,
+    ) -> Result<Option<String>> {
         let filtered_fragments: Vec<Fragment> = fragments
             .into_iter()
             .filter(|fragment| {
@@ -66,6 +70,7 @@ impl TypeScriptGenerator {
 
         let mut identifiers = Vec::new();
         let mut declarations = Vec::new();
+        let mut fragment_selectors = Vec::new();
         let mut processed_fragments = HashSet::new();
 
         for fragment in filtered_fragments {
@@ -77,11 +82,16 @@ impl TypeScriptGenerator {
             processed_fragments.insert(fragment_key);
 
             let name = fragment.name.clone().unwrap();
-            let use_explicit_identifier = name_counts.get(&name).unwrap_or(&0) > &1;
+            let use_explicit_identifier =
+                force_explicit_identifiers || name_counts.get(&name).unwrap_or(&0) > &1;
 
             let (identifier, declaration) =
                 Self::generate_fragment_declaration(&fragment, use_explicit_identifier)?;
 
+            if let Some(selector) = selectors::for_fragment(&fragment, &identifier) {
+                fragment_selectors.push(selector);
+            }
+
             identifiers.push(identifier);
             declarations.push(declaration);
         }
@@ -92,8 +102,39 @@ impl TypeScriptGenerator {
 
         let export_default = format!("export default [{}] as const;", identifiers.join(", "));
 
-        let file_content = format!("{}\n\n{}", declarations.join("\n\n"), export_default);
+        let mut sections = vec![declarations.join("\n\n")];
+        if let Some(selectors_export) = Self::generate_selectors_export(&fragment_selectors) {
+            sections.push(selectors_export);
+        }
+        sections.push(export_default);
+
+        Ok(Some(sections.join("\n\n")))
+    }
+
+    /// Emits a `selectors` export mapping each function/error/event
+    /// identifier to its canonical signature plus 4-byte selector (for
+    /// functions/errors) or 32-byte topic hash (for events).
+    fn generate_selectors_export(selectors: &[FragmentSelector]) -> Option<String> {
+        if selectors.is_empty() {
+            return None;
+        }
+
+        let entries: Vec<String> = selectors
+            .iter()
+            .map(|s| {
+                format!(
+                    "  {}: {{ signature: \"{}\", {}: \"{}\" }},",
+                    s.identifier,
+                    s.signature,
+                    s.kind.field_name(),
+                    s.hash
+                )
+            })
+            .collect();
 
-        Ok(Some(file_content))
+        Some(format!(
+            "export const selectors = {{\n{}\n}} as const;",
+            entries.join("\n")
+        ))
     }
 }
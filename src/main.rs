@@ -1,345 +1,157 @@
-use clap::Parser;
-use glob::glob;
-use rayon::prelude::*;
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::collections::{HashMap, HashSet};
-use std::fs::{self, create_dir_all};
-use std::path::{Path, PathBuf};
-use thiserror::Error;
+mod cache;
+mod check;
+mod config;
+mod error;
+mod fragment;
+mod generator;
+mod processor;
+mod selectors;
 
-#[derive(Error, Debug)]
-pub enum CodegenError {
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+use clap::{Args as ClapArgs, Parser, Subcommand};
 
-    #[error("JSON error: {0}")]
-    Json(#[from] serde_json::Error),
+use crate::config::Config;
+use crate::error::Result;
+use crate::processor::{AbiProcessor, ProcessorOptions};
 
-    #[error("Glob pattern error: {0}")]
-    Glob(#[from] glob::PatternError),
-
-    #[error("Missing fragment name")]
-    MissingName,
-
-    #[error("Processing error: {0}")]
-    Processing(String),
-}
-
-type Result<T> = std::result::Result<T, CodegenError>;
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
-struct FragmentInput {
-    #[serde(default)]
-    name: Option<String>,
-    #[serde(rename = "type")]
-    type_name: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    indexed: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    internal_type: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
-struct FragmentOutput {
-    #[serde(default)]
-    name: Option<String>,
-    #[serde(rename = "type")]
-    type_name: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    internal_type: Option<String>,
+#[derive(Parser, Debug)]
+#[command(version, about = "ABI to TypeScript code generator")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
-struct Fragment {
-    #[serde(default)]
-    name: Option<String>,
-    #[serde(rename = "type")]
-    type_name: String,
-    inputs: Vec<FragmentInput>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    outputs: Option<Vec<FragmentOutput>>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    state_mutability: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    anonymous: Option<bool>,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate TypeScript files from ABI JSON sources.
+    Generate(TargetArgs),
+    /// Verify that generated output is up to date, without writing anything.
+    Check(TargetArgs),
 }
 
-#[derive(Parser, Debug)]
-#[command(version, about = "ABI to TypeScript code generator")]
-struct Args {
+#[derive(ClapArgs, Debug, Clone)]
+struct TargetArgs {
     /// Source directory containing JSON ABI files
-    #[arg(short, long, required = true)]
-    source: String,
+    #[arg(short, long, required_unless_present = "config")]
+    source: Option<String>,
 
     /// Output directory for TypeScript files
     #[arg(short, long, default_value = "./abis")]
     out_dir: String,
-}
-
-impl Fragment {
-    fn get_unique_key(&self) -> String {
-        let name = self.name.clone().unwrap_or_default();
-
-        let mut input_types: Vec<String> = self
-            .inputs
-            .iter()
-            .map(|input| input.type_name.clone())
-            .collect();
-        input_types.sort();
-
-        let mut output_types: Vec<String> = if let Some(outputs) = &self.outputs {
-            outputs
-                .iter()
-                .map(|output| output.type_name.clone())
-                .collect()
-        } else {
-            Vec::new()
-        };
-        output_types.sort();
-
-        format!(
-            "{}:{}:{}:{}",
-            name,
-            self.type_name,
-            input_types.join(","),
-            output_types.join(",")
-        )
-    }
 
-    fn identifier(&self, use_explicit_identifier: bool) -> Result<String> {
-        let name = self
-            .name
-            .clone()
-            .filter(|n| !n.is_empty())
-            .ok_or(CodegenError::MissingName)?;
-
-        if !use_explicit_identifier {
-            return Ok(name);
-        }
-
-        let input_types = self
-            .inputs
-            .iter()
-            .map(|input| input.type_name.replace("[]", "Array"))
-            .collect::<Vec<String>>()
-            .join("_");
-
-        Ok(format!("{}_{}", name, input_types))
-    }
+    /// Path to an `adita.toml` describing multiple named targets. When set,
+    /// `--source`/`--out-dir` are ignored in favor of the targets it defines.
+    #[arg(short, long)]
+    config: Option<String>,
+
+    /// Skip rewriting output files whose content hasn't changed, tracked via
+    /// a `.adita-cache.json` manifest in each target's out-dir.
+    #[arg(long)]
+    incremental: bool,
+
+    /// When two source files would generate the same output path, nest them
+    /// under `out_dir` mirroring their source subdirectory instead of
+    /// erroring.
+    #[arg(long)]
+    mirror_structure: bool,
 }
 
-struct TypeScriptGenerator;
+struct ResolvedTarget {
+    /// `[[target]]` name from `adita.toml`, if this target came from a
+    /// config file rather than the plain `--source`/`--out-dir` flags.
+    name: Option<String>,
+    source_patterns: Vec<String>,
+    out_dir: String,
+    options: ProcessorOptions,
+}
 
-impl TypeScriptGenerator {
-    fn create_literal_for(value: &Value) -> String {
-        match value {
-            Value::Null => "null".to_string(),
-            Value::Bool(b) => b.to_string(),
-            Value::Number(n) => n.to_string(),
-            Value::String(s) => format!("\"{}\"", s.replace('\"', "\\\"")),
-            Value::Array(arr) => {
-                let elements: Vec<String> =
-                    arr.iter().map(|e| Self::create_literal_for(e)).collect();
-                format!("[{}]", elements.join(", "))
-            }
-            Value::Object(obj) => {
-                let properties: Vec<String> = obj
-                    .iter()
-                    .map(|(k, v)| format!("{}: {}", k, Self::create_literal_for(v)))
-                    .collect();
-                format!("{{{}}}", properties.join(", "))
-            }
+impl ResolvedTarget {
+    fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} ({})", name, self.out_dir),
+            None => self.out_dir.clone(),
         }
     }
+}
 
-    fn generate_fragment_declaration(
-        fragment: &Fragment,
-        use_explicit_identifier: bool,
-    ) -> Result<(String, String)> {
-        let identifier = fragment.identifier(use_explicit_identifier)?;
-
-        // Convert fragment to JSON Value for serialization
-        let fragment_value = serde_json::to_value(fragment)?;
-        let object_literal = Self::create_literal_for(&fragment_value);
-
-        let declaration = format!("export const {} = {} as const;", identifier, object_literal);
-
-        Ok((identifier, declaration))
-    }
+fn resolve_targets(args: &TargetArgs) -> Result<Vec<ResolvedTarget>> {
+    if let Some(config_path) = &args.config {
+        let config = Config::load(config_path)?;
 
-    fn generate_file_content(fragments: Vec<Fragment>) -> Result<Option<String>> {
-        let filtered_fragments: Vec<Fragment> = fragments
-            .into_iter()
-            .filter(|fragment| {
-                fragment.name.is_some() && !fragment.name.as_ref().unwrap().is_empty()
+        let targets = config
+            .targets
+            .iter()
+            .map(|target| ResolvedTarget {
+                name: Some(target.name.clone()),
+                source_patterns: target.source.clone(),
+                out_dir: target.out_dir.clone(),
+                options: ProcessorOptions {
+                    force_explicit_identifiers: target.force_explicit_identifiers,
+                    include: target.include.clone(),
+                    exclude: target.exclude.clone(),
+                    mirror_structure: target.mirror_structure || args.mirror_structure,
+                },
             })
             .collect();
 
-        if filtered_fragments.is_empty() {
-            return Ok(None);
-        }
-
-        // Count fragment names for disambiguation
-        let name_counts: HashMap<String, usize> = filtered_fragments
-            .iter()
-            .filter_map(|f| f.name.clone())
-            .fold(HashMap::new(), |mut counts, name| {
-                *counts.entry(name).or_insert(0) += 1;
-                counts
-            });
-
-        let mut identifiers = Vec::new();
-        let mut declarations = Vec::new();
-        let mut processed_fragments = HashSet::new();
-
-        for fragment in filtered_fragments {
-            let fragment_key = fragment.get_unique_key();
-
-            if processed_fragments.contains(&fragment_key) {
-                continue;
-            }
-            processed_fragments.insert(fragment_key);
-
-            let name = fragment.name.clone().unwrap();
-            let use_explicit_identifier = name_counts.get(&name).unwrap_or(&0) > &1;
-
-            let (identifier, declaration) =
-                Self::generate_fragment_declaration(&fragment, use_explicit_identifier)?;
-
-            identifiers.push(identifier);
-            declarations.push(declaration);
-        }
-
-        if identifiers.is_empty() {
-            return Ok(None);
-        }
-
-        let export_default = format!("export default [{}] as const;", identifiers.join(", "));
-
-        let file_content = format!("{}\n\n{}", declarations.join("\n\n"), export_default);
-
-        Ok(Some(file_content))
+        return Ok(targets);
     }
-}
 
-struct AbiProcessor {
-    out_dir: PathBuf,
-    abis_by_file: HashMap<PathBuf, Vec<Fragment>>,
+    // Implicit single-target fallback: use the plain --source/--out-dir flags.
+    let source = args
+        .source
+        .clone()
+        .expect("clap enforces --source without --config");
+
+    Ok(vec![ResolvedTarget {
+        name: None,
+        source_patterns: vec![format!("{}/**/*.json", source)],
+        out_dir: args.out_dir.clone(),
+        options: ProcessorOptions {
+            mirror_structure: args.mirror_structure,
+            ..ProcessorOptions::default()
+        },
+    }])
 }
 
-impl AbiProcessor {
-    fn new(out_dir: &str) -> Self {
-        Self {
-            out_dir: PathBuf::from(out_dir),
-            abis_by_file: HashMap::new(),
-        }
-    }
-
-    fn collect_abi_files(&mut self, source_pattern: &str) -> Result<()> {
-        let entries: Vec<PathBuf> = glob(source_pattern)?
-            .filter_map(|result| result.ok())
-            .filter(|path| !path.to_string_lossy().ends_with(".dbg.json"))
-            .collect();
-
-        // Process files in parallel
-        let results: Vec<Result<(PathBuf, Vec<Fragment>)>> = entries
-            .par_iter()
-            .map(|entry| self.extract_abis_from_file(entry))
-            .collect();
-
-        // Combine results
-        for result in results {
-            match result {
-                Ok((output_path, abis)) => {
-                    if !abis.is_empty() {
-                        self.abis_by_file
-                            .entry(output_path)
-                            .or_insert_with(Vec::new)
-                            .extend(abis);
-                    }
-                }
-                Err(e) => eprintln!("Error processing file: {}", e),
-            }
-        }
-
-        Ok(())
+fn generate(args: &TargetArgs) -> Result<()> {
+    for target in resolve_targets(args)? {
+        let mut processor = AbiProcessor::with_options(&target.out_dir, target.options);
+        processor.collect_abi_files(&target.source_patterns)?;
+        processor.generate_typescript_files(args.incremental)?;
     }
 
-    fn extract_abis_from_file(&self, path: &Path) -> Result<(PathBuf, Vec<Fragment>)> {
-        let file_content = fs::read_to_string(path)?;
-        let data: Value = serde_json::from_str(&file_content)?;
-
-        let abis = if let Some(Value::Array(abi_values)) = data.get("abi") {
-            abi_values
-                .iter()
-                .filter_map(|v| serde_json::from_value::<Fragment>(v.clone()).ok())
-                .collect()
-        } else {
-            Vec::new()
-        };
-
-        let file_name = path
-            .file_stem()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-
-        let output_path = self.out_dir.join(format!("{}.ts", file_name));
+    Ok(())
+}
 
-        Ok((output_path, abis))
-    }
+fn check(args: &TargetArgs) -> Result<bool> {
+    let mut clean = true;
 
-    fn deduplicate_abis(&self, abis: Vec<Fragment>) -> Vec<Fragment> {
-        let mut unique_abis = Vec::new();
-        let mut seen = HashSet::new();
+    for target in resolve_targets(args)? {
+        let mut processor = AbiProcessor::with_options(&target.out_dir, target.options);
+        processor.collect_abi_files(&target.source_patterns)?;
 
-        for abi in abis {
-            let key = abi.get_unique_key();
-            if !seen.contains(&key) {
-                seen.insert(key);
-                unique_abis.push(abi);
-            }
+        let report = processor.check()?;
+        if !report.is_clean() {
+            clean = false;
+            eprintln!("{} is out of date:", target.label());
+            eprint!("{}", report);
         }
-
-        unique_abis
     }
 
-    fn generate_typescript_files(&self) -> Result<()> {
-        // Create output directory if it doesn't exist
-        create_dir_all(&self.out_dir)?;
-
-        // Generate TypeScript files in parallel
-        self.abis_by_file
-            .par_iter()
-            .try_for_each(|(output_path, abis)| {
-                let unique_abis = self.deduplicate_abis(abis.clone());
-
-                match TypeScriptGenerator::generate_file_content(unique_abis)? {
-                    Some(content) => fs::write(output_path, content)?,
-                    None => (), // Skip empty files
-                }
-
-                Ok::<(), CodegenError>(())
-            })?;
-
-        Ok(())
-    }
+    Ok(clean)
 }
 
 fn main() -> Result<()> {
-    // Parse command line arguments
-    let args = Args::parse();
-
-    // Setup processor
-    let mut processor = AbiProcessor::new(&args.out_dir);
-
-    // Process source files
-    let source_pattern = format!("{}/**/*.json", args.source);
-    processor.collect_abi_files(&source_pattern)?;
-
-    // Generate TypeScript files
-    processor.generate_typescript_files()?;
-
-    Ok(())
+    let cli = Cli::parse();
+
+    match &cli.command {
+        Command::Generate(args) => generate(args),
+        Command::Check(args) => {
+            if check(args)? {
+                Ok(())
+            } else {
+                std::process::exit(1);
+            }
+        }
+    }
 }
@@ -5,42 +5,131 @@ use std::collections::{HashMap, HashSet};
 use std::fs::{self, create_dir_all};
 use std::path::{Path, PathBuf};
 
+use crate::cache::{CacheManifest, ContentHash, GENERATOR_VERSION};
+use crate::check::CheckReport;
 use crate::error::{CodegenError, Result};
 
 use crate::fragment::Fragment;
 
 use crate::generator::TypeScriptGenerator;
 
+/// Per-target generation options, set either from the single-target CLI
+/// flags or from a `[[target]]` table in `adita.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessorOptions {
+    pub force_explicit_identifiers: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Instead of erroring when two source files share a stem, nest the
+    /// output under `out_dir` mirroring each source's subdirectory path.
+    pub mirror_structure: bool,
+}
+
 pub struct AbiProcessor {
     out_dir: PathBuf,
+    options: ProcessorOptions,
     abis_by_file: HashMap<PathBuf, Vec<Fragment>>,
+    sources_by_output: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+/// Returns the directory a glob pattern is rooted at, i.e. everything before
+/// its first wildcard component. Used to compute a source's subdirectory
+/// path when mirroring directory structure under `out_dir`.
+fn glob_root(pattern: &str) -> PathBuf {
+    let wildcard_at = pattern.find(['*', '?', '[']).unwrap_or(pattern.len());
+    let prefix = &pattern[..wildcard_at];
+
+    // If the prefix already ends at a path boundary (e.g. "dir/" in
+    // "dir/**/*.json"), it's already a full directory — stripping it any
+    // further with `.parent()` would chop off a real directory component
+    // instead of just the wildcard's partial filename fragment.
+    let root = if prefix.is_empty() || prefix.ends_with(['/', '\\']) {
+        prefix.trim_end_matches(['/', '\\'])
+    } else {
+        Path::new(prefix)
+            .parent()
+            .and_then(Path::to_str)
+            .unwrap_or("")
+    };
+
+    if root.is_empty() {
+        PathBuf::from(".")
+    } else {
+        PathBuf::from(root)
+    }
 }
 
 impl AbiProcessor {
     pub fn new(out_dir: &str) -> Self {
+        Self::with_options(out_dir, ProcessorOptions::default())
+    }
+
+    pub fn with_options(out_dir: &str, options: ProcessorOptions) -> Self {
         Self {
             out_dir: PathBuf::from(out_dir),
+            options,
             abis_by_file: HashMap::new(),
+            sources_by_output: HashMap::new(),
+        }
+    }
+
+    /// Collects ABI files matching every glob in `source_patterns` into this
+    /// processor, then checks for cross-pattern output collisions once all
+    /// of a target's patterns have been merged in. Patterns belonging to the
+    /// same `[[target]]` must go through one call (or accumulate on the same
+    /// processor) so a file from one glob can't silently clobber one from
+    /// another glob of the same target.
+    pub fn collect_abi_files(&mut self, source_patterns: &[String]) -> Result<()> {
+        for source_pattern in source_patterns {
+            self.collect_pattern(source_pattern)?;
+        }
+
+        if !self.options.mirror_structure {
+            for (output, sources) in &self.sources_by_output {
+                if sources.len() > 1 {
+                    return Err(CodegenError::DuplicateOutput {
+                        output: output.clone(),
+                        sources: sources.clone(),
+                    });
+                }
+            }
         }
+
+        Ok(())
     }
 
-    pub fn collect_abi_files(&mut self, source_pattern: &str) -> Result<()> {
+    fn collect_pattern(&mut self, source_pattern: &str) -> Result<()> {
+        let source_root = glob_root(source_pattern);
+
         let entries: Vec<PathBuf> = glob(source_pattern)?
             .filter_map(|result| result.ok())
             .filter(|path| !path.to_string_lossy().ends_with(".dbg.json"))
             .collect();
 
         // Process files in parallel
-        let results: Vec<Result<(PathBuf, Vec<Fragment>)>> = entries
+        let results: Vec<Result<(PathBuf, PathBuf, Vec<Fragment>)>> = entries
             .par_iter()
-            .map(|entry| self.extract_abis_from_file(entry))
+            .map(|entry| self.extract_abis_from_file(entry, &source_root))
             .collect();
 
         // Combine results
         for result in results {
             match result {
-                Ok((output_path, abis)) => {
+                Ok((source_path, output_path, abis)) => {
+                    // Only files that actually contribute fragments count
+                    // toward an output; an empty or missing `abi` array
+                    // never gets written and shouldn't trip collision
+                    // detection against another file that shares its stem.
                     if !abis.is_empty() {
+                        let sources = self
+                            .sources_by_output
+                            .entry(output_path.clone())
+                            .or_insert_with(Vec::new);
+                        // The same file matched by two globs is one source.
+                        if !sources.contains(&source_path) {
+                            sources.push(source_path);
+                        }
+
                         self.abis_by_file
                             .entry(output_path)
                             .or_insert_with(Vec::new)
@@ -54,7 +143,27 @@ impl AbiProcessor {
         Ok(())
     }
 
-    pub fn extract_abis_from_file(&self, path: &Path) -> Result<(PathBuf, Vec<Fragment>)> {
+    fn fragment_allowed(&self, fragment: &Fragment) -> bool {
+        let included = self.options.include.is_empty()
+            || self
+                .options
+                .include
+                .iter()
+                .any(|t| t == &fragment.type_name);
+        let excluded = self
+            .options
+            .exclude
+            .iter()
+            .any(|t| t == &fragment.type_name);
+
+        included && !excluded
+    }
+
+    pub fn extract_abis_from_file(
+        &self,
+        path: &Path,
+        source_root: &Path,
+    ) -> Result<(PathBuf, PathBuf, Vec<Fragment>)> {
         let file_content = fs::read_to_string(path)?;
         let data: Value = serde_json::from_str(&file_content)?;
 
@@ -62,20 +171,25 @@ impl AbiProcessor {
             abi_values
                 .iter()
                 .filter_map(|v| serde_json::from_value::<Fragment>(v.clone()).ok())
+                .filter(|fragment| self.fragment_allowed(fragment))
                 .collect()
         } else {
             Vec::new()
         };
 
-        let file_name = path
-            .file_stem()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-
-        let output_path = self.out_dir.join(format!("{}.ts", file_name));
+        let output_path = if self.options.mirror_structure {
+            let relative = path.strip_prefix(source_root).unwrap_or(path);
+            self.out_dir.join(relative.with_extension("ts"))
+        } else {
+            let file_name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            self.out_dir.join(format!("{}.ts", file_name))
+        };
 
-        Ok((output_path, abis))
+        Ok((path.to_path_buf(), output_path, abis))
     }
 
     pub fn deduplicate_abis(&self, abis: Vec<Fragment>) -> Vec<Fragment> {
@@ -93,24 +207,142 @@ impl AbiProcessor {
         unique_abis
     }
 
-    pub fn generate_typescript_files(&self) -> Result<()> {
+    fn write_output(output_path: &Path, content: &str) -> Result<()> {
+        if let Some(parent) = output_path.parent() {
+            create_dir_all(parent)?;
+        }
+        fs::write(output_path, content)?;
+        Ok(())
+    }
+
+    pub fn generate_typescript_files(&self, incremental: bool) -> Result<()> {
         // Create output directory if it doesn't exist
         create_dir_all(&self.out_dir)?;
 
-        // Generate TypeScript files in parallel
-        self.abis_by_file
+        let cache = if incremental {
+            CacheManifest::load(&self.out_dir)
+        } else {
+            CacheManifest::default()
+        };
+
+        // Generate TypeScript files in parallel, recording the hashes of
+        // whatever was actually written (or would have been) so the cache
+        // can be persisted once every file has been handled.
+        let written: Vec<Option<(PathBuf, u128, u128)>> = self
+            .abis_by_file
             .par_iter()
-            .try_for_each(|(output_path, abis)| {
+            .map(|(output_path, abis)| {
                 let unique_abis = self.deduplicate_abis(abis.clone());
 
-                match TypeScriptGenerator::generate_file_content(unique_abis)? {
-                    Some(content) => fs::write(output_path, content)?,
-                    None => (), // Skip empty files
+                let mut sorted_keys: Vec<String> =
+                    unique_abis.iter().map(Fragment::get_unique_key).collect();
+                sorted_keys.sort();
+                let quick_hash = ContentHash::of(&(GENERATOR_VERSION, &sorted_keys)).into_inner();
+
+                if incremental && cache.quick_hash_matches(output_path, quick_hash) {
+                    // Quick hash alone doesn't capture every field that can
+                    // change generated output, so confirm against the full
+                    // content hash before trusting the file is up to date.
+                    // Both hashes only describe the cache manifest, not the
+                    // file system, so also require the output to still be
+                    // on disk before skipping the write.
+                    let content = TypeScriptGenerator::generate_file_content(
+                        unique_abis,
+                        self.options.force_explicit_identifiers,
+                    )?;
+                    return match content {
+                        Some(content) => {
+                            let content_hash = ContentHash::of(&content).into_inner();
+                            if output_path.exists()
+                                && cache.content_unchanged(output_path, content_hash)
+                            {
+                                Ok(None)
+                            } else {
+                                Self::write_output(output_path, &content)?;
+                                Ok(Some((output_path.clone(), quick_hash, content_hash)))
+                            }
+                        }
+                        None => Ok(None),
+                    };
                 }
 
-                Ok::<(), CodegenError>(())
-            })?;
+                match TypeScriptGenerator::generate_file_content(
+                    unique_abis,
+                    self.options.force_explicit_identifiers,
+                )? {
+                    Some(content) => {
+                        let content_hash = ContentHash::of(&content).into_inner();
+                        Self::write_output(output_path, &content)?;
+                        Ok(Some((output_path.clone(), quick_hash, content_hash)))
+                    }
+                    None => Ok(None),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if incremental {
+            let mut cache = cache;
+            for entry in written.into_iter().flatten() {
+                let (output_path, quick_hash, content_hash) = entry;
+                cache.record(output_path, quick_hash, content_hash);
+            }
+            cache.save(&self.out_dir)?;
+        }
 
         Ok(())
     }
+
+    /// Runs the same pipeline as `generate_typescript_files` in memory and
+    /// diffs it against what's on disk, without writing anything.
+    pub fn check(&self) -> Result<CheckReport> {
+        let mut report = CheckReport::default();
+        let mut produced = HashSet::new();
+
+        for (output_path, abis) in &self.abis_by_file {
+            let unique_abis = self.deduplicate_abis(abis.clone());
+
+            let content = match TypeScriptGenerator::generate_file_content(
+                unique_abis,
+                self.options.force_explicit_identifiers,
+            )? {
+                Some(content) => content,
+                None => continue,
+            };
+
+            produced.insert(output_path.clone());
+
+            match fs::read_to_string(output_path) {
+                Ok(existing) if existing == content => {}
+                Ok(_) => report.stale.push(output_path.clone()),
+                Err(_) => report.missing.push(output_path.clone()),
+            }
+        }
+
+        let mut existing_ts_files = Vec::new();
+        collect_ts_files(&self.out_dir, &mut existing_ts_files)?;
+        for path in existing_ts_files {
+            if !produced.contains(&path) {
+                report.orphaned.push(path);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+fn collect_ts_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_ts_files(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "ts") {
+            out.push(path);
+        }
+    }
+
+    Ok(())
 }
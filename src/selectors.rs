@@ -0,0 +1,130 @@
+use sha3::{Digest, Keccak256};
+
+use crate::fragment::{Fragment, FragmentInput};
+
+/// Distinguishes a function/error's 4-byte selector from an event's 32-byte
+/// topic hash, since both are "the keccak256 hash of the signature" but are
+/// conventionally exposed under different field names.
+pub enum SelectorKind {
+    Selector,
+    Topic,
+}
+
+impl SelectorKind {
+    pub fn field_name(&self) -> &'static str {
+        match self {
+            SelectorKind::Selector => "selector",
+            SelectorKind::Topic => "topic",
+        }
+    }
+}
+
+/// A fragment's canonical signature alongside its selector/topic, ready to
+/// be emitted as part of a generated file's `selectors` export.
+pub struct FragmentSelector {
+    pub identifier: String,
+    pub signature: String,
+    pub kind: SelectorKind,
+    pub hash: String,
+}
+
+/// Builds the canonical signature for a fragment, e.g.
+/// `transfer(address,uint256)`, reusing the same type-name values
+/// `Fragment::identifier` joins for explicit identifiers.
+///
+/// Returns `None` if any parameter is a `tuple` type with no `components`
+/// to expand it with, since there'd be no way to build a correct signature
+/// (and thus no correct selector) for it.
+pub fn signature(fragment: &Fragment) -> Option<String> {
+    let name = fragment.name.clone().unwrap_or_default();
+    let params = fragment
+        .inputs
+        .iter()
+        .map(input_signature)
+        .collect::<Option<Vec<_>>>()?
+        .join(",");
+
+    Some(format!("{}({})", name, params))
+}
+
+/// Returns the bracket/digit suffix following `tuple` in a type name (e.g.
+/// `""` for `tuple`, `"[]"` for `tuple[]`, `"[2][]"` for `tuple[2][]`), or
+/// `None` if `type_name` isn't a tuple type at all.
+fn tuple_suffix(type_name: &str) -> Option<&str> {
+    let suffix = type_name.strip_prefix("tuple")?;
+    suffix
+        .chars()
+        .all(|c| matches!(c, '[' | ']') || c.is_ascii_digit())
+        .then_some(suffix)
+}
+
+fn input_signature(input: &FragmentInput) -> Option<String> {
+    let Some(suffix) = tuple_suffix(&input.type_name) else {
+        return Some(input.type_name.clone());
+    };
+
+    let components = input.components.as_deref().unwrap_or_default();
+    if components.is_empty() {
+        return None;
+    }
+
+    let inner = components
+        .iter()
+        .map(input_signature)
+        .collect::<Option<Vec<_>>>()?
+        .join(",");
+
+    Some(format!("({}){}", inner, suffix))
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + bytes.len() * 2);
+    hex.push_str("0x");
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+/// `true` for fragment types that get a 4-byte selector (functions, errors);
+/// `false` for events, which get a full 32-byte topic hash instead.
+fn uses_short_selector(type_name: &str) -> bool {
+    matches!(type_name, "function" | "error")
+}
+
+pub fn for_fragment(fragment: &Fragment, identifier: &str) -> Option<FragmentSelector> {
+    if !matches!(fragment.type_name.as_str(), "function" | "error" | "event") {
+        return None;
+    }
+
+    let signature = match signature(fragment) {
+        Some(signature) => signature,
+        None => {
+            eprintln!(
+                "warning: skipping selector for `{}`: tuple parameter has no known components",
+                identifier
+            );
+            return None;
+        }
+    };
+    let full_hash = keccak256(signature.as_bytes());
+
+    let (kind, hash) = if uses_short_selector(&fragment.type_name) {
+        (SelectorKind::Selector, to_hex(&full_hash[..4]))
+    } else {
+        (SelectorKind::Topic, to_hex(&full_hash))
+    };
+
+    Some(FragmentSelector {
+        identifier: identifier.to_string(),
+        signature,
+        kind,
+        hash,
+    })
+}